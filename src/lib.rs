@@ -2,11 +2,155 @@ use std::hash::{Hash, Hasher};
 use std::vec::IntoIter;
 
 use im_rc::{HashMap, HashSet, Vector};
-use pyo3::exceptions::PyIndexError;
+use pyo3::exceptions::{PyIndexError, PyValueError};
 use pyo3::pyclass::CompareOp;
-use pyo3::types::{PyDict, PyTuple, PyType};
+use pyo3::types::{PyBytes, PyDict, PySlice, PyTuple, PyType};
 use pyo3::{exceptions::PyKeyError, types::PyMapping};
 use pyo3::{prelude::*, AsPyPointer};
+use serde_cbor::Value as CborValue;
+
+const CBOR_TAG_HASHMAP: i128 = 0;
+const CBOR_TAG_HASHSET: i128 = 1;
+const CBOR_TAG_VECTOR: i128 = 2;
+const CBOR_TAG_LEAF: i128 = 3;
+
+/// Encode an arbitrary Python object as a CBOR value: `HashMap`, `HashSet`
+/// and `Vector` recurse structurally, everything else (including the
+/// ordered and atomic imrc classes) is pickled and embedded as a tagged
+/// byte-string leaf.
+fn cbor_encode_any(py: Python, obj: &PyAny) -> PyResult<CborValue> {
+    if let Ok(map) = obj.extract::<PyRef<HashMapPy>>() {
+        cbor_encode_hashmap(py, &map)
+    } else if let Ok(set) = obj.extract::<PyRef<HashSetPy>>() {
+        cbor_encode_hashset(py, &set)
+    } else if let Ok(vector) = obj.extract::<PyRef<VectorPy>>() {
+        cbor_encode_vector(py, &vector)
+    } else {
+        let pickle = py.import("pickle")?;
+        let bytes: Vec<u8> = pickle.call_method1("dumps", (obj,))?.extract()?;
+        Ok(CborValue::Array(vec![
+            CborValue::Integer(CBOR_TAG_LEAF),
+            CborValue::Bytes(bytes),
+        ]))
+    }
+}
+
+fn cbor_decode_any(py: Python, value: &CborValue) -> PyResult<PyObject> {
+    let items = match value {
+        CborValue::Array(items) => items,
+        _ => return Err(PyValueError::new_err("malformed imrc CBOR value")),
+    };
+    let tag = match items.first() {
+        Some(CborValue::Integer(tag)) => *tag,
+        _ => return Err(PyValueError::new_err("malformed imrc CBOR tag")),
+    };
+    match tag {
+        CBOR_TAG_HASHMAP => Ok(cbor_decode_hashmap(py, value)?.into_py(py)),
+        CBOR_TAG_HASHSET => Ok(cbor_decode_hashset(py, value)?.into_py(py)),
+        CBOR_TAG_VECTOR => Ok(cbor_decode_vector(py, value)?.into_py(py)),
+        CBOR_TAG_LEAF => {
+            let bytes = match items.get(1) {
+                Some(CborValue::Bytes(bytes)) => bytes,
+                _ => return Err(PyValueError::new_err("malformed imrc CBOR leaf")),
+            };
+            let pickle = py.import("pickle")?;
+            Ok(pickle
+                .call_method1("loads", (PyBytes::new(py, bytes),))?
+                .into_py(py))
+        }
+        other => Err(PyValueError::new_err(format!(
+            "unknown imrc CBOR tag {}",
+            other
+        ))),
+    }
+}
+
+fn cbor_encode_hashmap(py: Python, map: &HashMapPy) -> PyResult<CborValue> {
+    let mut pairs = Vec::with_capacity(map.inner.len());
+    for (k, v) in map.inner.iter() {
+        let key = cbor_encode_any(py, k.into_py(py).as_ref(py))?;
+        let value = cbor_encode_any(py, v.as_ref(py))?;
+        pairs.push(CborValue::Array(vec![key, value]));
+    }
+    Ok(CborValue::Array(vec![
+        CborValue::Integer(CBOR_TAG_HASHMAP),
+        CborValue::Array(pairs),
+    ]))
+}
+
+fn cbor_decode_hashmap(py: Python, value: &CborValue) -> PyResult<HashMapPy> {
+    let pairs = match value {
+        CborValue::Array(items) => match items.get(1) {
+            Some(CborValue::Array(pairs)) => pairs,
+            _ => return Err(PyValueError::new_err("malformed imrc CBOR hash map")),
+        },
+        _ => return Err(PyValueError::new_err("malformed imrc CBOR hash map")),
+    };
+    let mut inner = HashMap::new();
+    for pair in pairs {
+        let (key_cbor, value_cbor) = match pair {
+            CborValue::Array(kv) if kv.len() == 2 => (&kv[0], &kv[1]),
+            _ => return Err(PyValueError::new_err("malformed imrc CBOR hash map entry")),
+        };
+        let key = Key::extract(cbor_decode_any(py, key_cbor)?.as_ref(py))?;
+        let value = cbor_decode_any(py, value_cbor)?;
+        inner.insert(key, value);
+    }
+    Ok(HashMapPy { inner })
+}
+
+fn cbor_encode_hashset(py: Python, set: &HashSetPy) -> PyResult<CborValue> {
+    let mut members = Vec::with_capacity(set.inner.len());
+    for k in set.inner.iter() {
+        members.push(cbor_encode_any(py, k.into_py(py).as_ref(py))?);
+    }
+    Ok(CborValue::Array(vec![
+        CborValue::Integer(CBOR_TAG_HASHSET),
+        CborValue::Array(members),
+    ]))
+}
+
+fn cbor_decode_hashset(py: Python, value: &CborValue) -> PyResult<HashSetPy> {
+    let members = match value {
+        CborValue::Array(items) => match items.get(1) {
+            Some(CborValue::Array(members)) => members,
+            _ => return Err(PyValueError::new_err("malformed imrc CBOR hash set")),
+        },
+        _ => return Err(PyValueError::new_err("malformed imrc CBOR hash set")),
+    };
+    let mut inner = HashSet::new();
+    for member in members {
+        let key = Key::extract(cbor_decode_any(py, member)?.as_ref(py))?;
+        inner.insert(key);
+    }
+    Ok(HashSetPy { inner })
+}
+
+fn cbor_encode_vector(py: Python, vector: &VectorPy) -> PyResult<CborValue> {
+    let mut elements = Vec::with_capacity(vector.inner.len());
+    for v in vector.inner.iter() {
+        elements.push(cbor_encode_any(py, v.as_ref(py))?);
+    }
+    Ok(CborValue::Array(vec![
+        CborValue::Integer(CBOR_TAG_VECTOR),
+        CborValue::Array(elements),
+    ]))
+}
+
+fn cbor_decode_vector(py: Python, value: &CborValue) -> PyResult<VectorPy> {
+    let elements = match value {
+        CborValue::Array(items) => match items.get(1) {
+            Some(CborValue::Array(elements)) => elements,
+            _ => return Err(PyValueError::new_err("malformed imrc CBOR vector")),
+        },
+        _ => return Err(PyValueError::new_err("malformed imrc CBOR vector")),
+    };
+    let mut inner = Vector::new();
+    for element in elements {
+        inner.push_back(cbor_decode_any(py, element)?);
+    }
+    Ok(VectorPy { inner })
+}
 
 #[derive(Clone, Debug)]
 struct Key {
@@ -234,6 +378,37 @@ impl HashMapPy {
         }
         Ok(HashMapPy { inner })
     }
+
+    fn __reduce__(&self, py: Python) -> PyResult<(PyObject, (PyObject,))> {
+        let dict = PyDict::new(py);
+        for (k, v) in self.inner.iter() {
+            dict.set_item(k.to_owned().into_py(py), v.clone_ref(py))?;
+        }
+        let cls = py.get_type::<HashMapPy>().into_py(py);
+        Ok((cls, (dict.into_py(py),)))
+    }
+
+    fn __copy__(slf: PyRef<'_, Self>) -> Py<Self> {
+        slf.into()
+    }
+
+    #[pyo3(signature = (_memo=None))]
+    fn __deepcopy__(slf: PyRef<'_, Self>, _memo: Option<&PyAny>) -> Py<Self> {
+        slf.into()
+    }
+
+    fn to_cbor(&self, py: Python) -> PyResult<Py<PyBytes>> {
+        let value = cbor_encode_hashmap(py, self)?;
+        let bytes = serde_cbor::to_vec(&value).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(PyBytes::new(py, &bytes).into())
+    }
+
+    #[classmethod]
+    fn from_cbor(_cls: &PyType, py: Python, bytes: &[u8]) -> PyResult<HashMapPy> {
+        let value: CborValue =
+            serde_cbor::from_slice(bytes).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        cbor_decode_hashmap(py, &value)
+    }
 }
 
 #[pyclass(module = "imrc", unsendable)]
@@ -273,6 +448,57 @@ fn is_subset(one: &HashSet<Key>, two: &HashSet<Key>) -> bool {
     one.iter().all(|v| two.contains(v))
 }
 
+fn set_difference(one: &HashSet<Key>, two: &HashSet<Key>) -> HashSet<Key> {
+    let mut inner = one.clone();
+    for value in two.iter() {
+        inner.remove(value);
+    }
+    inner
+}
+
+fn set_intersection(one: &HashSet<Key>, two: &HashSet<Key>) -> HashSet<Key> {
+    let mut inner: HashSet<Key> = HashSet::new();
+    let (larger, iter) = if one.len() > two.len() {
+        (one, two.iter())
+    } else {
+        (two, one.iter())
+    };
+    for value in iter {
+        if larger.contains(value) {
+            inner.insert(value.to_owned());
+        }
+    }
+    inner
+}
+
+fn set_symmetric_difference(one: &HashSet<Key>, two: &HashSet<Key>) -> HashSet<Key> {
+    let (mut inner, iter) = if one.len() > two.len() {
+        (one.clone(), two.iter())
+    } else {
+        (two.clone(), one.iter())
+    };
+    for value in iter {
+        if inner.contains(value) {
+            inner.remove(value);
+        } else {
+            inner.insert(value.to_owned());
+        }
+    }
+    inner
+}
+
+fn set_union(one: &HashSet<Key>, two: &HashSet<Key>) -> HashSet<Key> {
+    let (mut inner, iter) = if one.len() > two.len() {
+        (one.clone(), two.iter())
+    } else {
+        (two.clone(), one.iter())
+    };
+    for value in iter {
+        inner.insert(value.to_owned());
+    }
+    inner
+}
+
 #[pymethods]
 impl HashSetPy {
     #[new]
@@ -287,19 +513,27 @@ impl HashSetPy {
     }
 
     fn __and__(&self, other: &Self) -> Self {
-        self.intersection(&other)
+        HashSetPy {
+            inner: set_intersection(&self.inner, &other.inner),
+        }
     }
 
     fn __or__(&self, other: &Self) -> Self {
-        self.union(&other)
+        HashSetPy {
+            inner: set_union(&self.inner, &other.inner),
+        }
     }
 
     fn __sub__(&self, other: &Self) -> Self {
-        self.difference(&other)
+        HashSetPy {
+            inner: set_difference(&self.inner, &other.inner),
+        }
     }
 
     fn __xor__(&self, other: &Self) -> Self {
-        self.symmetric_difference(&other)
+        HashSetPy {
+            inner: set_symmetric_difference(&self.inner, &other.inner),
+        }
     }
 
     fn __iter__(slf: PyRef<'_, Self>) -> PyResult<Py<KeyIterator>> {
@@ -368,67 +602,47 @@ impl HashSetPy {
         }
     }
 
-    fn difference(&self, other: &Self) -> Self {
-        let mut inner = self.inner.clone();
-        for value in other.inner.iter() {
-            inner.remove(value);
-        }
-        HashSetPy { inner }
+    fn difference(&self, other: &PyAny) -> PyResult<Self> {
+        let other = HashSetPy::extract(other)?;
+        Ok(HashSetPy {
+            inner: set_difference(&self.inner, &other.inner),
+        })
     }
 
-    fn intersection(&self, other: &Self) -> Self {
-        let mut inner: HashSet<Key> = HashSet::new();
-        let larger: &HashSet<Key>;
-        let iter;
-        if self.inner.len() > other.inner.len() {
-            larger = &self.inner;
-            iter = other.inner.iter();
-        } else {
-            larger = &other.inner;
-            iter = self.inner.iter();
-        }
-        for value in iter {
-            if larger.contains(value) {
-                inner.insert(value.to_owned());
-            }
-        }
-        HashSetPy { inner }
+    fn intersection(&self, other: &PyAny) -> PyResult<Self> {
+        let other = HashSetPy::extract(other)?;
+        Ok(HashSetPy {
+            inner: set_intersection(&self.inner, &other.inner),
+        })
     }
 
-    fn symmetric_difference(&self, other: &Self) -> Self {
-        let mut inner: HashSet<Key>;
-        let iter;
-        if self.inner.len() > other.inner.len() {
-            inner = self.inner.clone();
-            iter = other.inner.iter();
-        } else {
-            inner = other.inner.clone();
-            iter = self.inner.iter();
-        }
-        for value in iter {
-            if inner.contains(value) {
-                inner.remove(value);
-            } else {
-                inner.insert(value.to_owned());
-            }
-        }
-        HashSetPy { inner }
+    fn symmetric_difference(&self, other: &PyAny) -> PyResult<Self> {
+        let other = HashSetPy::extract(other)?;
+        Ok(HashSetPy {
+            inner: set_symmetric_difference(&self.inner, &other.inner),
+        })
     }
 
-    fn union(&self, other: &Self) -> Self {
-        let mut inner: HashSet<Key>;
-        let iter;
-        if self.inner.len() > other.inner.len() {
-            inner = self.inner.clone();
-            iter = other.inner.iter();
-        } else {
-            inner = other.inner.clone();
-            iter = self.inner.iter();
-        }
-        for value in iter {
-            inner.insert(value.to_owned());
-        }
-        HashSetPy { inner }
+    fn union(&self, other: &PyAny) -> PyResult<Self> {
+        let other = HashSetPy::extract(other)?;
+        Ok(HashSetPy {
+            inner: set_union(&self.inner, &other.inner),
+        })
+    }
+
+    fn isdisjoint(&self, other: &PyAny) -> PyResult<bool> {
+        let other = HashSetPy::extract(other)?;
+        Ok(self.inner.iter().all(|v| !other.inner.contains(v)))
+    }
+
+    fn issubset(&self, other: &PyAny) -> PyResult<bool> {
+        let other = HashSetPy::extract(other)?;
+        Ok(is_subset(&self.inner, &other.inner))
+    }
+
+    fn issuperset(&self, other: &PyAny) -> PyResult<bool> {
+        let other = HashSetPy::extract(other)?;
+        Ok(is_subset(&other.inner, &self.inner))
     }
 
     #[pyo3(signature = (*iterables))]
@@ -442,6 +656,33 @@ impl HashSetPy {
         }
         Ok(HashSetPy { inner })
     }
+
+    fn __reduce__(&self, py: Python) -> PyResult<(PyObject, (Vec<Key>,))> {
+        let cls = py.get_type::<HashSetPy>().into_py(py);
+        Ok((cls, (self.inner.iter().map(|k| k.to_owned()).collect(),)))
+    }
+
+    fn __copy__(slf: PyRef<'_, Self>) -> Py<Self> {
+        slf.into()
+    }
+
+    #[pyo3(signature = (_memo=None))]
+    fn __deepcopy__(slf: PyRef<'_, Self>, _memo: Option<&PyAny>) -> Py<Self> {
+        slf.into()
+    }
+
+    fn to_cbor(&self, py: Python) -> PyResult<Py<PyBytes>> {
+        let value = cbor_encode_hashset(py, self)?;
+        let bytes = serde_cbor::to_vec(&value).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(PyBytes::new(py, &bytes).into())
+    }
+
+    #[classmethod]
+    fn from_cbor(_cls: &PyType, py: Python, bytes: &[u8]) -> PyResult<HashSetPy> {
+        let value: CborValue =
+            serde_cbor::from_slice(bytes).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        cbor_decode_hashset(py, &value)
+    }
 }
 
 #[repr(transparent)]
@@ -560,6 +801,150 @@ impl VectorPy {
         inner.pop_front();
         VectorPy { inner }
     }
+
+    fn __getitem__(&self, py: Python, index: &PyAny) -> PyResult<PyObject> {
+        if let Ok(slice) = index.downcast::<PySlice>() {
+            let indices = slice.indices(self.inner.len() as i64)?;
+            let mut inner = Vector::new();
+            let mut i = indices.start;
+            while (indices.step > 0 && i < indices.stop) || (indices.step < 0 && i > indices.stop) {
+                inner.push_back(self.inner.get(i as usize).unwrap().clone_ref(py));
+                i += indices.step;
+            }
+            Ok(VectorPy { inner }.into_py(py))
+        } else {
+            let mut index: isize = index.extract()?;
+            let len = self.inner.len() as isize;
+            if index < 0 {
+                index += len;
+            }
+            if index < 0 || index >= len {
+                Err(PyIndexError::new_err("Vector index out of range"))
+            } else {
+                Ok(self.inner.get(index as usize).unwrap().clone_ref(py))
+            }
+        }
+    }
+
+    fn push_back(&self, other: PyObject) -> VectorPy {
+        let mut inner = self.inner.clone();
+        inner.push_back(other);
+        VectorPy { inner }
+    }
+
+    fn pop_front(&self) -> PyResult<(PyObject, VectorPy)> {
+        let mut inner = self.inner.clone();
+        match inner.pop_front() {
+            Some(value) => Ok((value, VectorPy { inner })),
+            None => Err(PyIndexError::new_err("pop from empty Vector")),
+        }
+    }
+
+    fn pop_back(&self) -> PyResult<(PyObject, VectorPy)> {
+        let mut inner = self.inner.clone();
+        match inner.pop_back() {
+            Some(value) => Ok((value, VectorPy { inner })),
+            None => Err(PyIndexError::new_err("pop from empty Vector")),
+        }
+    }
+
+    fn set(&self, index: isize, value: PyObject) -> PyResult<VectorPy> {
+        let len = self.inner.len() as isize;
+        let index = if index < 0 { index + len } else { index };
+        if index < 0 || index >= len {
+            return Err(PyIndexError::new_err(
+                "Vector assignment index out of range",
+            ));
+        }
+        let mut inner = self.inner.clone();
+        inner.set(index as usize, value);
+        Ok(VectorPy { inner })
+    }
+
+    fn insert(&self, index: isize, value: PyObject) -> PyResult<VectorPy> {
+        let len = self.inner.len() as isize;
+        let index = if index < 0 { index + len } else { index };
+        if index < 0 || index > len {
+            return Err(PyIndexError::new_err("Vector insert index out of range"));
+        }
+        let mut inner = self.inner.clone();
+        inner.insert(index as usize, value);
+        Ok(VectorPy { inner })
+    }
+
+    fn __add__(&self, other: &Self) -> VectorPy {
+        let mut inner = self.inner.clone();
+        inner.append(other.inner.clone());
+        VectorPy { inner }
+    }
+
+    fn __mul__(&self, count: isize) -> VectorPy {
+        let mut inner = Vector::new();
+        for _ in 0..count.max(0) {
+            inner.append(self.inner.clone());
+        }
+        VectorPy { inner }
+    }
+
+    fn __rmul__(&self, count: isize) -> VectorPy {
+        self.__mul__(count)
+    }
+
+    fn index(&self, value: &PyAny) -> PyResult<usize> {
+        for (i, each) in self.inner.iter().enumerate() {
+            if PyAny::eq(each.extract(value.py())?, value)? {
+                return Ok(i);
+            }
+        }
+        Err(PyValueError::new_err("value not in Vector"))
+    }
+
+    fn count(&self, value: &PyAny) -> PyResult<usize> {
+        let mut total = 0;
+        for each in self.inner.iter() {
+            if PyAny::eq(each.extract(value.py())?, value)? {
+                total += 1;
+            }
+        }
+        Ok(total)
+    }
+
+    fn __contains__(&self, value: &PyAny) -> PyResult<bool> {
+        for each in self.inner.iter() {
+            if PyAny::eq(each.extract(value.py())?, value)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn __reduce__(&self, py: Python) -> PyResult<(PyObject, (Vec<PyObject>,))> {
+        let cls = py.get_type::<VectorPy>().into_py(py);
+        let elements = self.inner.iter().map(|v| v.clone_ref(py)).collect();
+        Ok((cls, (elements,)))
+    }
+
+    fn __copy__(slf: PyRef<'_, Self>) -> Py<Self> {
+        slf.into()
+    }
+
+    #[pyo3(signature = (_memo=None))]
+    fn __deepcopy__(slf: PyRef<'_, Self>, _memo: Option<&PyAny>) -> Py<Self> {
+        slf.into()
+    }
+
+    fn to_cbor(&self, py: Python) -> PyResult<Py<PyBytes>> {
+        let value = cbor_encode_vector(py, self)?;
+        let bytes = serde_cbor::to_vec(&value).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(PyBytes::new(py, &bytes).into())
+    }
+
+    #[classmethod]
+    fn from_cbor(_cls: &PyType, py: Python, bytes: &[u8]) -> PyResult<VectorPy> {
+        let value: CborValue =
+            serde_cbor::from_slice(bytes).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        cbor_decode_vector(py, &value)
+    }
 }
 
 #[pyclass(module = "imrc", unsendable)]
@@ -578,12 +963,1592 @@ impl VectorIterator {
     }
 }
 
-#[pymodule]
-#[pyo3(name = "imrc")]
-fn imrc(py: Python, m: &PyModule) -> PyResult<()> {
-    m.add_class::<HashMapPy>()?;
-    PyMapping::register::<HashMapPy>(py)?;
-    m.add_class::<HashSetPy>()?;
-    m.add_class::<VectorPy>()?;
-    Ok(())
+/// Rebuild `order`/`entries` from scratch, dropping tombstones and
+/// renumbering positions. Called once tombstones outnumber live entries.
+fn compact_ordered_map(
+    order: &Vector<Option<Key>>,
+    entries: &HashMap<Key, (usize, PyObject)>,
+) -> (Vector<Option<Key>>, HashMap<Key, (usize, PyObject)>) {
+    let mut new_order = Vector::new();
+    let mut new_entries = HashMap::new();
+    for slot in order.iter() {
+        if let Some(key) = slot {
+            if let Some((_, value)) = entries.get(key) {
+                let pos = new_order.len();
+                new_order.push_back(Some(key.to_owned()));
+                new_entries.insert(key.to_owned(), (pos, value.to_owned()));
+            }
+        }
+    }
+    (new_order, new_entries)
+}
+
+fn compact_ordered_set(
+    order: &Vector<Option<Key>>,
+    entries: &HashMap<Key, usize>,
+) -> (Vector<Option<Key>>, HashMap<Key, usize>) {
+    let mut new_order = Vector::new();
+    let mut new_entries = HashMap::new();
+    for slot in order.iter() {
+        if let Some(key) = slot {
+            if entries.contains_key(key) {
+                let pos = new_order.len();
+                new_order.push_back(Some(key.to_owned()));
+                new_entries.insert(key.to_owned(), pos);
+            }
+        }
+    }
+    (new_order, new_entries)
+}
+
+#[pyclass(name = "OrderedMap", module = "imrc", frozen, mapping, unsendable)]
+struct OrderedMapPy {
+    entries: HashMap<Key, (usize, PyObject)>,
+    order: Vector<Option<Key>>,
+    tombstones: usize,
+}
+
+impl OrderedMapPy {
+    fn empty() -> Self {
+        OrderedMapPy {
+            entries: HashMap::new(),
+            order: Vector::new(),
+            tombstones: 0,
+        }
+    }
+
+    fn inserted(&self, key: Key, value: PyObject) -> OrderedMapPy {
+        let mut entries = self.entries.clone();
+        let mut order = self.order.clone();
+        match entries.get(&key) {
+            Some((pos, _)) => {
+                let pos = *pos;
+                entries.insert(key, (pos, value));
+            }
+            None => {
+                let pos = order.len();
+                order.push_back(Some(key.clone()));
+                entries.insert(key, (pos, value));
+            }
+        }
+        OrderedMapPy {
+            entries,
+            order,
+            tombstones: self.tombstones,
+        }
+    }
+}
+
+impl<'source> FromPyObject<'source> for OrderedMapPy {
+    fn extract(ob: &'source PyAny) -> PyResult<Self> {
+        let mut ret = OrderedMapPy::empty();
+        if let Ok(mapping) = ob.downcast::<PyMapping>() {
+            for each in mapping.items()?.iter()? {
+                let (k, v): (Key, PyObject) = each?.extract()?;
+                ret = ret.inserted(k, v);
+            }
+        } else {
+            for each in ob.iter()? {
+                let (k, v): (Key, PyObject) = each?.extract()?;
+                ret = ret.inserted(k, v);
+            }
+        }
+        Ok(ret)
+    }
+}
+
+#[pymethods]
+impl OrderedMapPy {
+    #[new]
+    #[pyo3(signature = (value=None, **kwds))]
+    fn init(value: Option<OrderedMapPy>, kwds: Option<&PyDict>) -> PyResult<Self> {
+        let mut map = value.unwrap_or_else(OrderedMapPy::empty);
+        if let Some(kwds) = kwds {
+            for (k, v) in kwds {
+                map = map.inserted(Key::extract(k)?, v.into());
+            }
+        }
+        Ok(map)
+    }
+
+    fn __contains__(&self, key: Key) -> bool {
+        self.entries.contains_key(&key)
+    }
+
+    fn __iter__(slf: PyRef<'_, Self>) -> PyResult<Py<KeyIterator>> {
+        Py::new(
+            slf.py(),
+            KeyIterator {
+                inner: slf.keys().into_iter(),
+            },
+        )
+    }
+
+    fn __getitem__(&self, key: Key) -> PyResult<PyObject> {
+        match self.entries.get(&key) {
+            Some((_, value)) => Ok(value.to_owned()),
+            None => Err(PyKeyError::new_err(key)),
+        }
+    }
+
+    fn __len__(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn __repr__(&self, py: Python) -> String {
+        let contents = self.items().into_iter().map(|(k, v)| {
+            format!(
+                "{}: {}",
+                k.into_py(py),
+                v.call_method0(py, "__repr__")
+                    .and_then(|r| r.extract(py))
+                    .unwrap_or("<repr error>".to_owned())
+            )
+        });
+        format!(
+            "OrderedMap({{{}}})",
+            contents.collect::<Vec<_>>().join(", ")
+        )
+    }
+
+    fn __richcmp__(&self, other: &Self, op: CompareOp, py: Python<'_>) -> PyResult<PyObject> {
+        match op {
+            CompareOp::Eq => Ok((self.entries.len() == other.entries.len()
+                && self
+                    .entries
+                    .iter()
+                    .map(|(k, (_, v1))| (v1, other.entries.get(k).map(|(_, v2)| v2)))
+                    .map(|(v1, v2)| PyAny::eq(v1.extract(py)?, v2))
+                    .all(|r| r.unwrap_or(false)))
+            .into_py(py)),
+            CompareOp::Ne => Ok((self.entries.len() != other.entries.len()
+                || self
+                    .entries
+                    .iter()
+                    .map(|(k, (_, v1))| (v1, other.entries.get(k).map(|(_, v2)| v2)))
+                    .map(|(v1, v2)| PyAny::ne(v1.extract(py)?, v2))
+                    .any(|r| r.unwrap_or(true)))
+            .into_py(py)),
+            _ => Ok(py.NotImplemented()),
+        }
+    }
+
+    fn get(&self, key: Key) -> Option<PyObject> {
+        self.entries.get(&key).map(|(_, value)| value.to_owned())
+    }
+
+    fn keys(&self) -> Vec<Key> {
+        self.order
+            .iter()
+            .filter_map(|slot| slot.to_owned())
+            .collect()
+    }
+
+    fn values(&self) -> Vec<PyObject> {
+        self.keys()
+            .into_iter()
+            .filter_map(|k| self.entries.get(&k).map(|(_, v)| v.to_owned()))
+            .collect()
+    }
+
+    fn items(&self) -> Vec<(Key, PyObject)> {
+        self.keys()
+            .into_iter()
+            .filter_map(|k| self.entries.get(&k).map(|(_, v)| (k.clone(), v.to_owned())))
+            .collect()
+    }
+
+    fn discard(&self, key: Key) -> OrderedMapPy {
+        match self.remove(key) {
+            Ok(map) => map,
+            Err(_) => OrderedMapPy {
+                entries: self.entries.clone(),
+                order: self.order.clone(),
+                tombstones: self.tombstones,
+            },
+        }
+    }
+
+    fn insert(&self, key: Key, value: &PyAny) -> OrderedMapPy {
+        self.inserted(key, value.into())
+    }
+
+    fn remove(&self, key: Key) -> PyResult<OrderedMapPy> {
+        match self.entries.get(&key) {
+            Some((pos, _)) => {
+                let pos = *pos;
+                let mut entries = self.entries.clone();
+                entries.remove(&key);
+                let mut order = self.order.clone();
+                order.set(pos, None);
+                let tombstones = self.tombstones + 1;
+                let should_compact = tombstones * 2 > order.len();
+                let (order, entries, tombstones) = if should_compact {
+                    let (order, entries) = compact_ordered_map(&order, &entries);
+                    (order, entries, 0)
+                } else {
+                    (order, entries, tombstones)
+                };
+                Ok(OrderedMapPy {
+                    entries,
+                    order,
+                    tombstones,
+                })
+            }
+            None => Err(PyKeyError::new_err(key)),
+        }
+    }
+
+    #[pyo3(signature = (*maps, **kwds))]
+    fn update(&self, maps: &PyTuple, kwds: Option<&PyDict>) -> PyResult<OrderedMapPy> {
+        let mut map = OrderedMapPy {
+            entries: self.entries.clone(),
+            order: self.order.clone(),
+            tombstones: self.tombstones,
+        };
+        for value in maps {
+            let other = OrderedMapPy::extract(value)?;
+            for (k, v) in other.items() {
+                map = map.inserted(k, v);
+            }
+        }
+        if let Some(kwds) = kwds {
+            for (k, v) in kwds {
+                map = map.inserted(Key::extract(k)?, v.extract()?);
+            }
+        }
+        Ok(map)
+    }
+}
+
+#[pyclass(name = "OrderedSet", module = "imrc", frozen, unsendable)]
+struct OrderedSetPy {
+    entries: HashMap<Key, usize>,
+    order: Vector<Option<Key>>,
+    tombstones: usize,
+}
+
+impl OrderedSetPy {
+    fn empty() -> Self {
+        OrderedSetPy {
+            entries: HashMap::new(),
+            order: Vector::new(),
+            tombstones: 0,
+        }
+    }
+
+    fn inserted(&self, value: Key) -> OrderedSetPy {
+        if self.entries.contains_key(&value) {
+            return OrderedSetPy {
+                entries: self.entries.clone(),
+                order: self.order.clone(),
+                tombstones: self.tombstones,
+            };
+        }
+        let mut entries = self.entries.clone();
+        let mut order = self.order.clone();
+        let pos = order.len();
+        order.push_back(Some(value.clone()));
+        entries.insert(value, pos);
+        OrderedSetPy {
+            entries,
+            order,
+            tombstones: self.tombstones,
+        }
+    }
+}
+
+impl<'source> FromPyObject<'source> for OrderedSetPy {
+    fn extract(ob: &'source PyAny) -> PyResult<Self> {
+        let mut ret = OrderedSetPy::empty();
+        for each in ob.iter()? {
+            ret = ret.inserted(each?.extract()?);
+        }
+        Ok(ret)
+    }
+}
+
+#[pymethods]
+impl OrderedSetPy {
+    #[new]
+    fn init(value: Option<OrderedSetPy>) -> Self {
+        value.unwrap_or_else(OrderedSetPy::empty)
+    }
+
+    fn __contains__(&self, value: Key) -> bool {
+        self.entries.contains_key(&value)
+    }
+
+    fn __iter__(slf: PyRef<'_, Self>) -> PyResult<Py<KeyIterator>> {
+        let keys = slf
+            .order
+            .iter()
+            .filter_map(|slot| slot.to_owned())
+            .collect::<Vec<_>>();
+        Py::new(
+            slf.py(),
+            KeyIterator {
+                inner: keys.into_iter(),
+            },
+        )
+    }
+
+    fn __len__(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn __repr__(&self, py: Python) -> String {
+        let contents = self
+            .order
+            .iter()
+            .filter_map(|slot| slot.to_owned())
+            .map(|k| {
+                k.into_py(py)
+                    .call_method0(py, "__repr__")
+                    .and_then(|r| r.extract(py))
+                    .unwrap_or("<repr failed>".to_owned())
+            });
+        format!(
+            "OrderedSet({{{}}})",
+            contents.collect::<Vec<_>>().join(", ")
+        )
+    }
+
+    fn __richcmp__(&self, other: &Self, op: CompareOp, py: Python<'_>) -> PyResult<PyObject> {
+        let mut self_set: HashSet<Key> = HashSet::new();
+        for key in self.entries.keys() {
+            self_set.insert(key.to_owned());
+        }
+        let mut other_set: HashSet<Key> = HashSet::new();
+        for key in other.entries.keys() {
+            other_set.insert(key.to_owned());
+        }
+        match op {
+            CompareOp::Eq => Ok((self_set.len() == other_set.len()
+                && is_subset(&self_set, &other_set))
+            .into_py(py)),
+            CompareOp::Ne => Ok((self_set.len() != other_set.len()
+                || !is_subset(&self_set, &other_set))
+            .into_py(py)),
+            CompareOp::Lt => Ok((self_set.len() < other_set.len()
+                && is_subset(&self_set, &other_set))
+            .into_py(py)),
+            CompareOp::Le => Ok(is_subset(&self_set, &other_set).into_py(py)),
+            _ => Ok(py.NotImplemented()),
+        }
+    }
+
+    fn insert(&self, value: Key) -> OrderedSetPy {
+        self.inserted(value)
+    }
+
+    fn discard(&self, value: Key) -> OrderedSetPy {
+        match self.remove(value) {
+            Ok(set) => set,
+            Err(_) => OrderedSetPy {
+                entries: self.entries.clone(),
+                order: self.order.clone(),
+                tombstones: self.tombstones,
+            },
+        }
+    }
+
+    fn remove(&self, value: Key) -> PyResult<OrderedSetPy> {
+        match self.entries.get(&value) {
+            Some(pos) => {
+                let pos = *pos;
+                let mut entries = self.entries.clone();
+                entries.remove(&value);
+                let mut order = self.order.clone();
+                order.set(pos, None);
+                let tombstones = self.tombstones + 1;
+                let should_compact = tombstones * 2 > order.len();
+                let (order, entries, tombstones) = if should_compact {
+                    let (order, entries) = compact_ordered_set(&order, &entries);
+                    (order, entries, 0)
+                } else {
+                    (order, entries, tombstones)
+                };
+                Ok(OrderedSetPy {
+                    entries,
+                    order,
+                    tombstones,
+                })
+            }
+            None => Err(PyKeyError::new_err(value)),
+        }
+    }
+
+    #[pyo3(signature = (*iterables))]
+    fn update(&self, iterables: &PyTuple) -> PyResult<OrderedSetPy> {
+        let mut set = OrderedSetPy {
+            entries: self.entries.clone(),
+            order: self.order.clone(),
+            tombstones: self.tombstones,
+        };
+        for each in iterables {
+            for value in each.iter()? {
+                set = set.inserted(value?.extract()?);
+            }
+        }
+        Ok(set)
+    }
+}
+
+/// `Arc`-backed counterparts of `HashMap`/`HashSet`/`Vector`.
+///
+/// `im_rc`'s containers use `Rc` internally, so pyo3 has to mark them
+/// `unsendable` and any access from a thread other than the one that
+/// created them panics at runtime. The `im` crate builds the same
+/// persistent data structures on `Arc`, which makes them `Send + Sync`
+/// and safe to share across threads (`concurrent.futures`, free-threaded
+/// CPython, sub-interpreters, ...). `Key`'s `Hash`/`PartialEq` already
+/// re-acquire the GIL before touching Python objects, so it is reused
+/// unchanged here; the atomic refcounting in `Arc` costs a little more
+/// per clone than `Rc`'s non-atomic bump, so prefer the plain classes
+/// above when a structure never leaves its creating thread.
+mod atomic {
+    use im::{HashMap, HashSet, Vector};
+    use pyo3::exceptions::{PyIndexError, PyKeyError, PyValueError};
+    use pyo3::prelude::*;
+    use pyo3::pyclass::CompareOp;
+    use pyo3::types::{PyBytes, PyDict, PyMapping, PySlice, PyTuple, PyType};
+
+    use super::{
+        CborValue, Key, CBOR_TAG_HASHMAP, CBOR_TAG_HASHSET, CBOR_TAG_LEAF, CBOR_TAG_VECTOR,
+    };
+
+    /// Encode an arbitrary Python object as a CBOR value for the atomic
+    /// (`Arc`-backed) containers: atomic imrc containers recurse
+    /// structurally, everything else is pickled and embedded as a tagged
+    /// byte-string leaf, mirroring `super::cbor_encode_any`.
+    fn cbor_encode_any(py: Python, obj: &PyAny) -> PyResult<CborValue> {
+        if let Ok(map) = obj.extract::<PyRef<AtomicHashMapPy>>() {
+            cbor_encode_hashmap(py, &map)
+        } else if let Ok(set) = obj.extract::<PyRef<AtomicHashSetPy>>() {
+            cbor_encode_hashset(py, &set)
+        } else if let Ok(vector) = obj.extract::<PyRef<AtomicVectorPy>>() {
+            cbor_encode_vector(py, &vector)
+        } else {
+            let pickle = py.import("pickle")?;
+            let bytes: Vec<u8> = pickle.call_method1("dumps", (obj,))?.extract()?;
+            Ok(CborValue::Array(vec![
+                CborValue::Integer(CBOR_TAG_LEAF),
+                CborValue::Bytes(bytes),
+            ]))
+        }
+    }
+
+    fn cbor_decode_any(py: Python, value: &CborValue) -> PyResult<PyObject> {
+        let items = match value {
+            CborValue::Array(items) => items,
+            _ => return Err(PyValueError::new_err("malformed imrc CBOR value")),
+        };
+        let tag = match items.first() {
+            Some(CborValue::Integer(tag)) => *tag,
+            _ => return Err(PyValueError::new_err("malformed imrc CBOR tag")),
+        };
+        match tag {
+            CBOR_TAG_HASHMAP => Ok(cbor_decode_hashmap(py, value)?.into_py(py)),
+            CBOR_TAG_HASHSET => Ok(cbor_decode_hashset(py, value)?.into_py(py)),
+            CBOR_TAG_VECTOR => Ok(cbor_decode_vector(py, value)?.into_py(py)),
+            CBOR_TAG_LEAF => {
+                let bytes = match items.get(1) {
+                    Some(CborValue::Bytes(bytes)) => bytes,
+                    _ => return Err(PyValueError::new_err("malformed imrc CBOR leaf")),
+                };
+                let pickle = py.import("pickle")?;
+                Ok(pickle
+                    .call_method1("loads", (PyBytes::new(py, bytes),))?
+                    .into_py(py))
+            }
+            other => Err(PyValueError::new_err(format!(
+                "unknown imrc CBOR tag {}",
+                other
+            ))),
+        }
+    }
+
+    fn cbor_encode_hashmap(py: Python, map: &AtomicHashMapPy) -> PyResult<CborValue> {
+        let mut pairs = Vec::with_capacity(map.inner.len());
+        for (k, v) in map.inner.iter() {
+            let key = cbor_encode_any(py, k.to_owned().into_py(py).as_ref(py))?;
+            let value = cbor_encode_any(py, v.as_ref(py))?;
+            pairs.push(CborValue::Array(vec![key, value]));
+        }
+        Ok(CborValue::Array(vec![
+            CborValue::Integer(CBOR_TAG_HASHMAP),
+            CborValue::Array(pairs),
+        ]))
+    }
+
+    fn cbor_decode_hashmap(py: Python, value: &CborValue) -> PyResult<AtomicHashMapPy> {
+        let pairs = match value {
+            CborValue::Array(items) => match items.get(1) {
+                Some(CborValue::Array(pairs)) => pairs,
+                _ => return Err(PyValueError::new_err("malformed imrc CBOR hash map")),
+            },
+            _ => return Err(PyValueError::new_err("malformed imrc CBOR hash map")),
+        };
+        let mut inner = HashMap::new();
+        for pair in pairs {
+            let (key_cbor, value_cbor) = match pair {
+                CborValue::Array(kv) if kv.len() == 2 => (&kv[0], &kv[1]),
+                _ => return Err(PyValueError::new_err("malformed imrc CBOR hash map entry")),
+            };
+            let key = Key::extract(cbor_decode_any(py, key_cbor)?.as_ref(py))?;
+            let value = cbor_decode_any(py, value_cbor)?;
+            inner.insert(key, value);
+        }
+        Ok(AtomicHashMapPy { inner })
+    }
+
+    fn cbor_encode_hashset(py: Python, set: &AtomicHashSetPy) -> PyResult<CborValue> {
+        let mut members = Vec::with_capacity(set.inner.len());
+        for k in set.inner.iter() {
+            members.push(cbor_encode_any(py, k.to_owned().into_py(py).as_ref(py))?);
+        }
+        Ok(CborValue::Array(vec![
+            CborValue::Integer(CBOR_TAG_HASHSET),
+            CborValue::Array(members),
+        ]))
+    }
+
+    fn cbor_decode_hashset(py: Python, value: &CborValue) -> PyResult<AtomicHashSetPy> {
+        let members = match value {
+            CborValue::Array(items) => match items.get(1) {
+                Some(CborValue::Array(members)) => members,
+                _ => return Err(PyValueError::new_err("malformed imrc CBOR hash set")),
+            },
+            _ => return Err(PyValueError::new_err("malformed imrc CBOR hash set")),
+        };
+        let mut inner = HashSet::new();
+        for member in members {
+            let key = Key::extract(cbor_decode_any(py, member)?.as_ref(py))?;
+            inner.insert(key);
+        }
+        Ok(AtomicHashSetPy { inner })
+    }
+
+    fn cbor_encode_vector(py: Python, vector: &AtomicVectorPy) -> PyResult<CborValue> {
+        let mut elements = Vec::with_capacity(vector.inner.len());
+        for v in vector.inner.iter() {
+            elements.push(cbor_encode_any(py, v.as_ref(py))?);
+        }
+        Ok(CborValue::Array(vec![
+            CborValue::Integer(CBOR_TAG_VECTOR),
+            CborValue::Array(elements),
+        ]))
+    }
+
+    fn cbor_decode_vector(py: Python, value: &CborValue) -> PyResult<AtomicVectorPy> {
+        let elements = match value {
+            CborValue::Array(items) => match items.get(1) {
+                Some(CborValue::Array(elements)) => elements,
+                _ => return Err(PyValueError::new_err("malformed imrc CBOR vector")),
+            },
+            _ => return Err(PyValueError::new_err("malformed imrc CBOR vector")),
+        };
+        let mut inner = Vector::new();
+        for element in elements {
+            inner.push_back(cbor_decode_any(py, element)?);
+        }
+        Ok(AtomicVectorPy { inner })
+    }
+
+    fn set_difference(one: &HashSet<Key>, two: &HashSet<Key>) -> HashSet<Key> {
+        let mut inner = one.clone();
+        for value in two.iter() {
+            inner.remove(value);
+        }
+        inner
+    }
+
+    fn set_intersection(one: &HashSet<Key>, two: &HashSet<Key>) -> HashSet<Key> {
+        let mut inner: HashSet<Key> = HashSet::new();
+        let (larger, iter) = if one.len() > two.len() {
+            (one, two.iter())
+        } else {
+            (two, one.iter())
+        };
+        for value in iter {
+            if larger.contains(value) {
+                inner.insert(value.to_owned());
+            }
+        }
+        inner
+    }
+
+    fn set_symmetric_difference(one: &HashSet<Key>, two: &HashSet<Key>) -> HashSet<Key> {
+        let (mut inner, iter) = if one.len() > two.len() {
+            (one.clone(), two.iter())
+        } else {
+            (two.clone(), one.iter())
+        };
+        for value in iter {
+            if inner.contains(value) {
+                inner.remove(value);
+            } else {
+                inner.insert(value.to_owned());
+            }
+        }
+        inner
+    }
+
+    fn set_union(one: &HashSet<Key>, two: &HashSet<Key>) -> HashSet<Key> {
+        let (mut inner, iter) = if one.len() > two.len() {
+            (one.clone(), two.iter())
+        } else {
+            (two.clone(), one.iter())
+        };
+        for value in iter {
+            inner.insert(value.to_owned());
+        }
+        inner
+    }
+
+    #[repr(transparent)]
+    #[pyclass(name = "AtomicHashMap", module = "imrc", frozen, mapping)]
+    pub struct AtomicHashMapPy {
+        inner: HashMap<Key, PyObject>,
+    }
+
+    impl<'source> FromPyObject<'source> for AtomicHashMapPy {
+        fn extract(ob: &'source PyAny) -> PyResult<Self> {
+            let mut ret = HashMap::new();
+            if let Ok(mapping) = ob.downcast::<PyMapping>() {
+                for each in mapping.items()?.iter()? {
+                    let (k, v): (Key, PyObject) = each?.extract()?;
+                    ret.insert(k, v);
+                }
+            } else {
+                for each in ob.iter()? {
+                    let (k, v): (Key, PyObject) = each?.extract()?;
+                    ret.insert(k, v);
+                }
+            }
+            Ok(AtomicHashMapPy { inner: ret })
+        }
+    }
+
+    #[pymethods]
+    impl AtomicHashMapPy {
+        #[new]
+        #[pyo3(signature = (value=None, **kwds))]
+        fn init(value: Option<AtomicHashMapPy>, kwds: Option<&PyDict>) -> PyResult<Self> {
+            let mut map = value.unwrap_or_else(|| AtomicHashMapPy {
+                inner: HashMap::new(),
+            });
+            if let Some(kwds) = kwds {
+                for (k, v) in kwds {
+                    map.inner.insert(Key::extract(k)?, v.into());
+                }
+            }
+            Ok(map)
+        }
+
+        fn __contains__(&self, key: Key) -> bool {
+            self.inner.contains_key(&key)
+        }
+
+        fn __iter__(slf: PyRef<'_, Self>) -> PyResult<Py<AtomicKeyIterator>> {
+            let keys = slf.inner.keys().map(|k| k.to_owned()).collect::<Vec<_>>();
+            Py::new(
+                slf.py(),
+                AtomicKeyIterator {
+                    inner: keys.into_iter(),
+                },
+            )
+        }
+
+        fn __getitem__(&self, key: Key) -> PyResult<PyObject> {
+            match self.inner.get(&key) {
+                Some(value) => Ok(value.to_owned()),
+                None => Err(PyKeyError::new_err(key)),
+            }
+        }
+
+        fn __len__(&self) -> usize {
+            self.inner.len()
+        }
+
+        fn __repr__(&self, py: Python) -> String {
+            let contents = self.inner.iter().map(|(k, v)| {
+                format!(
+                    "{}: {}",
+                    k.to_owned().into_py(py),
+                    v.call_method0(py, "__repr__")
+                        .and_then(|r| r.extract(py))
+                        .unwrap_or("<repr error>".to_owned())
+                )
+            });
+            format!(
+                "AtomicHashMap({{{}}})",
+                contents.collect::<Vec<_>>().join(", ")
+            )
+        }
+
+        fn __richcmp__(&self, other: &Self, op: CompareOp, py: Python<'_>) -> PyResult<PyObject> {
+            match op {
+                CompareOp::Eq => Ok((self.inner.len() == other.inner.len()
+                    && self
+                        .inner
+                        .iter()
+                        .map(|(k1, v1)| (v1, other.inner.get(k1)))
+                        .map(|(v1, v2)| PyAny::eq(v1.extract(py)?, v2))
+                        .all(|r| r.unwrap_or(false)))
+                .into_py(py)),
+                CompareOp::Ne => Ok((self.inner.len() != other.inner.len()
+                    || self
+                        .inner
+                        .iter()
+                        .map(|(k1, v1)| (v1, other.inner.get(k1)))
+                        .map(|(v1, v2)| PyAny::ne(v1.extract(py)?, v2))
+                        .all(|r| r.unwrap_or(true)))
+                .into_py(py)),
+                _ => Ok(py.NotImplemented()),
+            }
+        }
+
+        fn get(&self, key: Key) -> Option<&PyObject> {
+            self.inner.get(&key)
+        }
+
+        fn keys(&self) -> Vec<Key> {
+            self.inner.keys().map(|key| key.to_owned()).collect()
+        }
+
+        fn values(&self) -> Vec<&PyObject> {
+            self.inner.values().collect()
+        }
+
+        fn items(&self) -> Vec<(&Key, &PyObject)> {
+            self.inner.iter().collect()
+        }
+
+        fn discard(&self, key: Key) -> PyResult<AtomicHashMapPy> {
+            match self.inner.contains_key(&key) {
+                true => Ok(AtomicHashMapPy {
+                    inner: self.inner.without(&key),
+                }),
+                false => Ok(AtomicHashMapPy {
+                    inner: self.inner.clone(),
+                }),
+            }
+        }
+
+        fn insert(&self, key: Key, value: &PyAny) -> AtomicHashMapPy {
+            AtomicHashMapPy {
+                inner: self.inner.update(key, value.into()),
+            }
+        }
+
+        fn remove(&self, key: Key) -> PyResult<AtomicHashMapPy> {
+            match self.inner.contains_key(&key) {
+                true => Ok(AtomicHashMapPy {
+                    inner: self.inner.without(&key),
+                }),
+                false => Err(PyKeyError::new_err(key)),
+            }
+        }
+
+        #[pyo3(signature = (*maps, **kwds))]
+        fn update(&self, maps: &PyTuple, kwds: Option<&PyDict>) -> PyResult<AtomicHashMapPy> {
+            let mut inner = self.inner.clone();
+            for value in maps {
+                let map = AtomicHashMapPy::extract(value)?;
+                for (k, v) in &map.inner {
+                    inner.insert(k.to_owned(), v.to_owned());
+                }
+            }
+            if let Some(kwds) = kwds {
+                for (k, v) in kwds {
+                    inner.insert(Key::extract(k)?, v.extract()?);
+                }
+            }
+            Ok(AtomicHashMapPy { inner })
+        }
+
+        fn __reduce__(&self, py: Python) -> PyResult<(PyObject, (PyObject,))> {
+            let dict = PyDict::new(py);
+            for (k, v) in self.inner.iter() {
+                dict.set_item(k.to_owned().into_py(py), v.clone_ref(py))?;
+            }
+            let cls = py.get_type::<AtomicHashMapPy>().into_py(py);
+            Ok((cls, (dict.into_py(py),)))
+        }
+
+        fn __copy__(slf: PyRef<'_, Self>) -> Py<Self> {
+            slf.into()
+        }
+
+        #[pyo3(signature = (_memo=None))]
+        fn __deepcopy__(slf: PyRef<'_, Self>, _memo: Option<&PyAny>) -> Py<Self> {
+            slf.into()
+        }
+
+        fn to_cbor(&self, py: Python) -> PyResult<Py<PyBytes>> {
+            let value = cbor_encode_hashmap(py, self)?;
+            let bytes =
+                serde_cbor::to_vec(&value).map_err(|e| PyValueError::new_err(e.to_string()))?;
+            Ok(PyBytes::new(py, &bytes).into())
+        }
+
+        #[classmethod]
+        fn from_cbor(_cls: &PyType, py: Python, bytes: &[u8]) -> PyResult<AtomicHashMapPy> {
+            let value: CborValue =
+                serde_cbor::from_slice(bytes).map_err(|e| PyValueError::new_err(e.to_string()))?;
+            cbor_decode_hashmap(py, &value)
+        }
+    }
+
+    #[pyclass(module = "imrc")]
+    pub struct AtomicKeyIterator {
+        inner: std::vec::IntoIter<Key>,
+    }
+
+    #[pymethods]
+    impl AtomicKeyIterator {
+        fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+            slf
+        }
+
+        fn __next__(mut slf: PyRefMut<'_, Self>) -> Option<Key> {
+            slf.inner.next()
+        }
+    }
+
+    #[repr(transparent)]
+    #[pyclass(name = "AtomicHashSet", module = "imrc", frozen)]
+    pub struct AtomicHashSetPy {
+        inner: HashSet<Key>,
+    }
+
+    impl<'source> FromPyObject<'source> for AtomicHashSetPy {
+        fn extract(ob: &'source PyAny) -> PyResult<Self> {
+            let mut ret = HashSet::new();
+            for each in ob.iter()? {
+                let k: Key = each?.extract()?;
+                ret.insert(k);
+            }
+            Ok(AtomicHashSetPy { inner: ret })
+        }
+    }
+
+    fn is_subset(one: &HashSet<Key>, two: &HashSet<Key>) -> bool {
+        one.iter().all(|v| two.contains(v))
+    }
+
+    #[pymethods]
+    impl AtomicHashSetPy {
+        #[new]
+        fn init(value: Option<AtomicHashSetPy>) -> Self {
+            value.unwrap_or_else(|| AtomicHashSetPy {
+                inner: HashSet::new(),
+            })
+        }
+
+        fn __and__(&self, other: &Self) -> Self {
+            let mut inner: HashSet<Key> = HashSet::new();
+            for value in self.inner.iter() {
+                if other.inner.contains(value) {
+                    inner.insert(value.to_owned());
+                }
+            }
+            AtomicHashSetPy { inner }
+        }
+
+        fn __or__(&self, other: &Self) -> Self {
+            let mut inner = self.inner.clone();
+            for value in other.inner.iter() {
+                inner.insert(value.to_owned());
+            }
+            AtomicHashSetPy { inner }
+        }
+
+        fn __sub__(&self, other: &Self) -> Self {
+            let mut inner = self.inner.clone();
+            for value in other.inner.iter() {
+                inner.remove(value);
+            }
+            AtomicHashSetPy { inner }
+        }
+
+        fn __xor__(&self, other: &Self) -> Self {
+            let mut inner = self.inner.clone();
+            for value in other.inner.iter() {
+                if inner.contains(value) {
+                    inner.remove(value);
+                } else {
+                    inner.insert(value.to_owned());
+                }
+            }
+            AtomicHashSetPy { inner }
+        }
+
+        fn __iter__(slf: PyRef<'_, Self>) -> PyResult<Py<AtomicKeyIterator>> {
+            let iter = slf
+                .inner
+                .iter()
+                .map(|k| k.to_owned())
+                .collect::<Vec<_>>()
+                .into_iter();
+            Py::new(slf.py(), AtomicKeyIterator { inner: iter })
+        }
+
+        fn __len__(&self) -> usize {
+            self.inner.len()
+        }
+
+        fn __repr__(&self, py: Python) -> String {
+            let contents = self.inner.iter().map(|k| {
+                k.to_owned()
+                    .into_py(py)
+                    .call_method0(py, "__repr__")
+                    .and_then(|r| r.extract(py))
+                    .unwrap_or("<repr failed>".to_owned())
+            });
+            format!(
+                "AtomicHashSet({{{}}})",
+                contents.collect::<Vec<_>>().join(", ")
+            )
+        }
+
+        fn __richcmp__(&self, other: &Self, op: CompareOp, py: Python<'_>) -> PyResult<PyObject> {
+            match op {
+                CompareOp::Eq => Ok((self.inner.len() == other.inner.len()
+                    && is_subset(&self.inner, &other.inner))
+                .into_py(py)),
+                CompareOp::Ne => Ok((self.inner.len() != other.inner.len()
+                    || self.inner.iter().any(|k| !other.inner.contains(k)))
+                .into_py(py)),
+                CompareOp::Lt => Ok((self.inner.len() < other.inner.len()
+                    && is_subset(&self.inner, &other.inner))
+                .into_py(py)),
+                CompareOp::Le => Ok(is_subset(&self.inner, &other.inner).into_py(py)),
+                _ => Ok(py.NotImplemented()),
+            }
+        }
+
+        fn insert(&self, value: Key) -> AtomicHashSetPy {
+            AtomicHashSetPy {
+                inner: self.inner.update(value),
+            }
+        }
+
+        fn discard(&self, value: Key) -> PyResult<AtomicHashSetPy> {
+            match self.inner.contains(&value) {
+                true => Ok(AtomicHashSetPy {
+                    inner: self.inner.without(&value),
+                }),
+                false => Ok(AtomicHashSetPy {
+                    inner: self.inner.clone(),
+                }),
+            }
+        }
+
+        fn remove(&self, value: Key) -> PyResult<AtomicHashSetPy> {
+            match self.inner.contains(&value) {
+                true => Ok(AtomicHashSetPy {
+                    inner: self.inner.without(&value),
+                }),
+                false => Err(PyKeyError::new_err(value)),
+            }
+        }
+
+        #[pyo3(signature = (*iterables))]
+        fn update(&self, iterables: &PyTuple) -> PyResult<AtomicHashSetPy> {
+            let mut inner = self.inner.clone();
+            for each in iterables {
+                for value in each.iter()? {
+                    inner.insert(Key::extract(value?)?);
+                }
+            }
+            Ok(AtomicHashSetPy { inner })
+        }
+
+        fn difference(&self, other: &PyAny) -> PyResult<Self> {
+            let other = AtomicHashSetPy::extract(other)?;
+            Ok(AtomicHashSetPy {
+                inner: set_difference(&self.inner, &other.inner),
+            })
+        }
+
+        fn intersection(&self, other: &PyAny) -> PyResult<Self> {
+            let other = AtomicHashSetPy::extract(other)?;
+            Ok(AtomicHashSetPy {
+                inner: set_intersection(&self.inner, &other.inner),
+            })
+        }
+
+        fn symmetric_difference(&self, other: &PyAny) -> PyResult<Self> {
+            let other = AtomicHashSetPy::extract(other)?;
+            Ok(AtomicHashSetPy {
+                inner: set_symmetric_difference(&self.inner, &other.inner),
+            })
+        }
+
+        fn union(&self, other: &PyAny) -> PyResult<Self> {
+            let other = AtomicHashSetPy::extract(other)?;
+            Ok(AtomicHashSetPy {
+                inner: set_union(&self.inner, &other.inner),
+            })
+        }
+
+        fn isdisjoint(&self, other: &PyAny) -> PyResult<bool> {
+            let other = AtomicHashSetPy::extract(other)?;
+            Ok(self.inner.iter().all(|v| !other.inner.contains(v)))
+        }
+
+        fn issubset(&self, other: &PyAny) -> PyResult<bool> {
+            let other = AtomicHashSetPy::extract(other)?;
+            Ok(is_subset(&self.inner, &other.inner))
+        }
+
+        fn issuperset(&self, other: &PyAny) -> PyResult<bool> {
+            let other = AtomicHashSetPy::extract(other)?;
+            Ok(is_subset(&other.inner, &self.inner))
+        }
+
+        fn __reduce__(&self, py: Python) -> PyResult<(PyObject, (Vec<Key>,))> {
+            let cls = py.get_type::<AtomicHashSetPy>().into_py(py);
+            Ok((cls, (self.inner.iter().map(|k| k.to_owned()).collect(),)))
+        }
+
+        fn __copy__(slf: PyRef<'_, Self>) -> Py<Self> {
+            slf.into()
+        }
+
+        #[pyo3(signature = (_memo=None))]
+        fn __deepcopy__(slf: PyRef<'_, Self>, _memo: Option<&PyAny>) -> Py<Self> {
+            slf.into()
+        }
+
+        fn to_cbor(&self, py: Python) -> PyResult<Py<PyBytes>> {
+            let value = cbor_encode_hashset(py, self)?;
+            let bytes =
+                serde_cbor::to_vec(&value).map_err(|e| PyValueError::new_err(e.to_string()))?;
+            Ok(PyBytes::new(py, &bytes).into())
+        }
+
+        #[classmethod]
+        fn from_cbor(_cls: &PyType, py: Python, bytes: &[u8]) -> PyResult<AtomicHashSetPy> {
+            let value: CborValue =
+                serde_cbor::from_slice(bytes).map_err(|e| PyValueError::new_err(e.to_string()))?;
+            cbor_decode_hashset(py, &value)
+        }
+    }
+
+    #[repr(transparent)]
+    #[pyclass(name = "AtomicVector", module = "imrc", frozen, sequence)]
+    pub struct AtomicVectorPy {
+        inner: Vector<PyObject>,
+    }
+
+    impl<'source> FromPyObject<'source> for AtomicVectorPy {
+        fn extract(ob: &'source PyAny) -> PyResult<Self> {
+            let mut ret: Vector<PyObject> = Vector::new();
+            for each in ob.iter()? {
+                ret.push_back(each?.extract()?);
+            }
+            Ok(AtomicVectorPy { inner: ret })
+        }
+    }
+
+    #[pymethods]
+    impl AtomicVectorPy {
+        #[new]
+        #[pyo3(signature = (*elements))]
+        fn init(elements: &PyTuple) -> PyResult<Self> {
+            let mut ret: AtomicVectorPy;
+            if elements.len() == 1 {
+                ret = elements.get_item(0)?.extract()?;
+            } else {
+                ret = AtomicVectorPy {
+                    inner: Vector::new(),
+                };
+                for each in elements {
+                    ret.inner.push_back(each.extract()?);
+                }
+            }
+            Ok(ret)
+        }
+
+        fn __len__(&self) -> usize {
+            self.inner.len()
+        }
+
+        fn __repr__(&self, py: Python) -> String {
+            let contents = self.inner.iter().map(|v| {
+                v.call_method0(py, "__repr__")
+                    .and_then(|r| r.extract(py))
+                    .unwrap_or("<repr failed>".to_owned())
+            });
+            format!(
+                "AtomicVector([{}])",
+                contents.collect::<Vec<_>>().join(", ")
+            )
+        }
+
+        fn __reversed__(&self) -> Self {
+            let mut inner = Vector::new();
+            for each in self.inner.iter() {
+                inner.push_front(each.to_owned())
+            }
+            AtomicVectorPy { inner }
+        }
+
+        fn __richcmp__(&self, other: &Self, op: CompareOp, py: Python<'_>) -> PyResult<PyObject> {
+            match op {
+                CompareOp::Eq => Ok((self.inner.len() == other.inner.len()
+                    && self
+                        .inner
+                        .iter()
+                        .zip(other.inner.iter())
+                        .map(|(e1, e2)| PyAny::eq(e1.extract(py)?, e2))
+                        .all(|r| r.unwrap_or(false)))
+                .into_py(py)),
+                CompareOp::Ne => Ok((self.inner.len() != other.inner.len()
+                    || self
+                        .inner
+                        .iter()
+                        .zip(other.inner.iter())
+                        .map(|(e1, e2)| PyAny::ne(e1.extract(py)?, e2))
+                        .any(|r| r.unwrap_or(true)))
+                .into_py(py)),
+                _ => Ok(py.NotImplemented()),
+            }
+        }
+
+        fn __iter__(slf: PyRef<'_, Self>) -> PyResult<Py<AtomicVectorIterator>> {
+            let iter = slf
+                .inner
+                .iter()
+                .map(|v| v.to_owned())
+                .collect::<Vec<_>>()
+                .into_iter();
+            Py::new(slf.py(), AtomicVectorIterator { inner: iter })
+        }
+
+        #[getter]
+        fn first(&self) -> PyResult<&PyObject> {
+            self.inner
+                .front()
+                .ok_or_else(|| PyIndexError::new_err("empty list has no first element"))
+        }
+
+        fn push_front(&self, other: PyObject) -> AtomicVectorPy {
+            let mut inner = self.inner.clone();
+            inner.push_front(other);
+            AtomicVectorPy { inner }
+        }
+
+        #[getter]
+        fn rest(&self) -> AtomicVectorPy {
+            let mut inner = self.inner.clone();
+            inner.pop_front();
+            AtomicVectorPy { inner }
+        }
+
+        fn __getitem__(&self, py: Python, index: &PyAny) -> PyResult<PyObject> {
+            if let Ok(slice) = index.downcast::<PySlice>() {
+                let indices = slice.indices(self.inner.len() as i64)?;
+                let mut inner = Vector::new();
+                let mut i = indices.start;
+                while (indices.step > 0 && i < indices.stop)
+                    || (indices.step < 0 && i > indices.stop)
+                {
+                    inner.push_back(self.inner.get(i as usize).unwrap().clone_ref(py));
+                    i += indices.step;
+                }
+                Ok(AtomicVectorPy { inner }.into_py(py))
+            } else {
+                let mut index: isize = index.extract()?;
+                let len = self.inner.len() as isize;
+                if index < 0 {
+                    index += len;
+                }
+                if index < 0 || index >= len {
+                    Err(PyIndexError::new_err("Vector index out of range"))
+                } else {
+                    Ok(self.inner.get(index as usize).unwrap().clone_ref(py))
+                }
+            }
+        }
+
+        fn push_back(&self, other: PyObject) -> AtomicVectorPy {
+            let mut inner = self.inner.clone();
+            inner.push_back(other);
+            AtomicVectorPy { inner }
+        }
+
+        fn pop_front(&self) -> PyResult<(PyObject, AtomicVectorPy)> {
+            let mut inner = self.inner.clone();
+            match inner.pop_front() {
+                Some(value) => Ok((value, AtomicVectorPy { inner })),
+                None => Err(PyIndexError::new_err("pop from empty Vector")),
+            }
+        }
+
+        fn pop_back(&self) -> PyResult<(PyObject, AtomicVectorPy)> {
+            let mut inner = self.inner.clone();
+            match inner.pop_back() {
+                Some(value) => Ok((value, AtomicVectorPy { inner })),
+                None => Err(PyIndexError::new_err("pop from empty Vector")),
+            }
+        }
+
+        fn set(&self, index: isize, value: PyObject) -> PyResult<AtomicVectorPy> {
+            let len = self.inner.len() as isize;
+            let index = if index < 0 { index + len } else { index };
+            if index < 0 || index >= len {
+                return Err(PyIndexError::new_err(
+                    "Vector assignment index out of range",
+                ));
+            }
+            let mut inner = self.inner.clone();
+            inner.set(index as usize, value);
+            Ok(AtomicVectorPy { inner })
+        }
+
+        fn insert(&self, index: isize, value: PyObject) -> PyResult<AtomicVectorPy> {
+            let len = self.inner.len() as isize;
+            let index = if index < 0 { index + len } else { index };
+            if index < 0 || index > len {
+                return Err(PyIndexError::new_err("Vector insert index out of range"));
+            }
+            let mut inner = self.inner.clone();
+            inner.insert(index as usize, value);
+            Ok(AtomicVectorPy { inner })
+        }
+
+        fn __add__(&self, other: &Self) -> AtomicVectorPy {
+            let mut inner = self.inner.clone();
+            inner.append(other.inner.clone());
+            AtomicVectorPy { inner }
+        }
+
+        fn __mul__(&self, count: isize) -> AtomicVectorPy {
+            let mut inner = Vector::new();
+            for _ in 0..count.max(0) {
+                inner.append(self.inner.clone());
+            }
+            AtomicVectorPy { inner }
+        }
+
+        fn __rmul__(&self, count: isize) -> AtomicVectorPy {
+            self.__mul__(count)
+        }
+
+        fn index(&self, value: &PyAny) -> PyResult<usize> {
+            for (i, each) in self.inner.iter().enumerate() {
+                if PyAny::eq(each.extract(value.py())?, value)? {
+                    return Ok(i);
+                }
+            }
+            Err(PyValueError::new_err("value not in Vector"))
+        }
+
+        fn count(&self, value: &PyAny) -> PyResult<usize> {
+            let mut total = 0;
+            for each in self.inner.iter() {
+                if PyAny::eq(each.extract(value.py())?, value)? {
+                    total += 1;
+                }
+            }
+            Ok(total)
+        }
+
+        fn __contains__(&self, value: &PyAny) -> PyResult<bool> {
+            for each in self.inner.iter() {
+                if PyAny::eq(each.extract(value.py())?, value)? {
+                    return Ok(true);
+                }
+            }
+            Ok(false)
+        }
+
+        fn __reduce__(&self, py: Python) -> PyResult<(PyObject, (Vec<PyObject>,))> {
+            let cls = py.get_type::<AtomicVectorPy>().into_py(py);
+            let elements = self.inner.iter().map(|v| v.clone_ref(py)).collect();
+            Ok((cls, (elements,)))
+        }
+
+        fn __copy__(slf: PyRef<'_, Self>) -> Py<Self> {
+            slf.into()
+        }
+
+        #[pyo3(signature = (_memo=None))]
+        fn __deepcopy__(slf: PyRef<'_, Self>, _memo: Option<&PyAny>) -> Py<Self> {
+            slf.into()
+        }
+
+        fn to_cbor(&self, py: Python) -> PyResult<Py<PyBytes>> {
+            let value = cbor_encode_vector(py, self)?;
+            let bytes =
+                serde_cbor::to_vec(&value).map_err(|e| PyValueError::new_err(e.to_string()))?;
+            Ok(PyBytes::new(py, &bytes).into())
+        }
+
+        #[classmethod]
+        fn from_cbor(_cls: &PyType, py: Python, bytes: &[u8]) -> PyResult<AtomicVectorPy> {
+            let value: CborValue =
+                serde_cbor::from_slice(bytes).map_err(|e| PyValueError::new_err(e.to_string()))?;
+            cbor_decode_vector(py, &value)
+        }
+    }
+
+    #[pyclass(module = "imrc")]
+    pub struct AtomicVectorIterator {
+        inner: std::vec::IntoIter<PyObject>,
+    }
+
+    #[pymethods]
+    impl AtomicVectorIterator {
+        fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+            slf
+        }
+
+        fn __next__(mut slf: PyRefMut<'_, Self>) -> Option<PyObject> {
+            slf.inner.next()
+        }
+    }
+}
+
+#[pymodule]
+#[pyo3(name = "imrc")]
+fn imrc(py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_class::<HashMapPy>()?;
+    PyMapping::register::<HashMapPy>(py)?;
+    m.add_class::<HashSetPy>()?;
+    m.add_class::<VectorPy>()?;
+    m.add_class::<OrderedMapPy>()?;
+    PyMapping::register::<OrderedMapPy>(py)?;
+    m.add_class::<OrderedSetPy>()?;
+    m.add_class::<atomic::AtomicHashMapPy>()?;
+    PyMapping::register::<atomic::AtomicHashMapPy>(py)?;
+    m.add_class::<atomic::AtomicHashSetPy>()?;
+    m.add_class::<atomic::AtomicVectorPy>()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::types::IntoPyDict;
+
+    /// Build a throwaway `imrc` module object and register it under
+    /// `sys.modules` so `pickle` can resolve our pyclasses by
+    /// `__module__`/`__qualname__` when dumping/loading real instances.
+    fn with_imrc_module<F: FnOnce(Python, &PyModule)>(f: F) {
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "imrc").unwrap();
+            imrc(py, module).unwrap();
+            py.import("sys")
+                .unwrap()
+                .getattr("modules")
+                .unwrap()
+                .set_item("imrc", module)
+                .unwrap();
+            f(py, module);
+        });
+    }
+
+    #[test]
+    fn hashmap_pickle_round_trips_with_non_string_keys_and_nested_containers() {
+        with_imrc_module(|py, module| {
+            let locals = [("imrc", module)].into_py_dict(py);
+            let map: Py<HashMapPy> = py
+                .eval(
+                    "imrc.HashMap({1: 'one', (2, 3): imrc.Vector(1, 2, 3)})",
+                    None,
+                    Some(locals),
+                )
+                .unwrap()
+                .extract()
+                .unwrap();
+            let pickle = py.import("pickle").unwrap();
+            let dumped = pickle.call_method1("dumps", (map.clone_ref(py),)).unwrap();
+            let loaded: Py<HashMapPy> = pickle
+                .call_method1("loads", (dumped,))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert!(map.as_ref(py).eq(loaded.as_ref(py)).unwrap());
+        });
+    }
+
+    #[test]
+    fn hashset_pickle_round_trips() {
+        with_imrc_module(|py, module| {
+            let locals = [("imrc", module)].into_py_dict(py);
+            let set: Py<HashSetPy> = py
+                .eval("imrc.HashSet([1, 'two', (3, 4)])", None, Some(locals))
+                .unwrap()
+                .extract()
+                .unwrap();
+            let pickle = py.import("pickle").unwrap();
+            let dumped = pickle.call_method1("dumps", (set.clone_ref(py),)).unwrap();
+            let loaded: Py<HashSetPy> = pickle
+                .call_method1("loads", (dumped,))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert!(set.as_ref(py).eq(loaded.as_ref(py)).unwrap());
+        });
+    }
+
+    #[test]
+    fn vector_pickle_round_trips_with_nested_container() {
+        with_imrc_module(|py, module| {
+            let locals = [("imrc", module)].into_py_dict(py);
+            let vector: Py<VectorPy> = py
+                .eval(
+                    "imrc.Vector(1, 2, imrc.HashMap({'a': 1}))",
+                    None,
+                    Some(locals),
+                )
+                .unwrap()
+                .extract()
+                .unwrap();
+            let pickle = py.import("pickle").unwrap();
+            let dumped = pickle
+                .call_method1("dumps", (vector.clone_ref(py),))
+                .unwrap();
+            let loaded: Py<VectorPy> = pickle
+                .call_method1("loads", (dumped,))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert!(vector.as_ref(py).eq(loaded.as_ref(py)).unwrap());
+        });
+    }
+
+    #[test]
+    fn copy_and_deepcopy_return_an_equal_structure_for_all_three_types() {
+        with_imrc_module(|py, module| {
+            let locals = [("imrc", module)].into_py_dict(py);
+            for expr in [
+                "imrc.HashMap({'a': 1, 'b': 2})",
+                "imrc.HashSet([1, 2, 3])",
+                "imrc.Vector(1, 2, 3)",
+            ] {
+                let original = py.eval(expr, None, Some(locals)).unwrap();
+                let copy_mod = py.import("copy").unwrap();
+                let shallow = copy_mod.call_method1("copy", (original,)).unwrap();
+                let deep = copy_mod.call_method1("deepcopy", (original,)).unwrap();
+                assert!(original.eq(shallow).unwrap());
+                assert!(original.eq(deep).unwrap());
+            }
+        });
+    }
+
+    #[test]
+    fn deepcopy_shares_mutable_leaf_objects() {
+        // Per the request, these frozen containers implement __deepcopy__
+        // by returning the same structure rather than rebuilding from
+        // deep-copied leaves, so a mutable leaf (like a list) is *not*
+        // isolated by copy.deepcopy. This documents that trade-off.
+        with_imrc_module(|py, module| {
+            let locals = [("imrc", module)].into_py_dict(py);
+            let map = py
+                .eval("imrc.HashMap({'leaf': [1, 2, 3]})", None, Some(locals))
+                .unwrap();
+            let copy_mod = py.import("copy").unwrap();
+            let deep = copy_mod.call_method1("deepcopy", (map,)).unwrap();
+            let original_leaf = map.get_item("leaf").unwrap();
+            let copied_leaf = deep.get_item("leaf").unwrap();
+            original_leaf.call_method1("append", (4,)).unwrap();
+            assert!(copied_leaf.eq(original_leaf).unwrap());
+        });
+    }
+
+    #[test]
+    fn cbor_round_trips_empty_containers() {
+        with_imrc_module(|py, module| {
+            let locals = [("imrc", module)].into_py_dict(py);
+            for (expr, type_name) in [
+                ("imrc.HashMap()", "HashMap"),
+                ("imrc.HashSet()", "HashSet"),
+                ("imrc.Vector()", "Vector"),
+            ] {
+                let original = py.eval(expr, None, Some(locals)).unwrap();
+                let bytes = original.call_method0("to_cbor").unwrap();
+                let cls = module.getattr(type_name).unwrap();
+                let loaded = cls.call_method1("from_cbor", (bytes,)).unwrap();
+                assert!(original.eq(loaded).unwrap());
+            }
+        });
+    }
+
+    #[test]
+    fn cbor_round_trips_nested_imrc_containers() {
+        with_imrc_module(|py, module| {
+            let locals = [("imrc", module)].into_py_dict(py);
+            let vector = py
+                .eval(
+                    "imrc.Vector([imrc.HashMap({'a': imrc.HashSet([1, 2])})])",
+                    None,
+                    Some(locals),
+                )
+                .unwrap();
+            let bytes = vector.call_method0("to_cbor").unwrap();
+            let loaded = module
+                .getattr("Vector")
+                .unwrap()
+                .call_method1("from_cbor", (bytes,))
+                .unwrap();
+            assert!(vector.eq(loaded).unwrap());
+        });
+    }
+
+    #[test]
+    fn cbor_round_trips_pickled_leaf_objects() {
+        with_imrc_module(|py, module| {
+            let locals = [("imrc", module)].into_py_dict(py);
+            let map = py
+                .eval(
+                    "imrc.HashMap({'point': (1, 2, 3), 'tags': ['x', 'y']})",
+                    None,
+                    Some(locals),
+                )
+                .unwrap();
+            let bytes = map.call_method0("to_cbor").unwrap();
+            let loaded = module
+                .getattr("HashMap")
+                .unwrap()
+                .call_method1("from_cbor", (bytes,))
+                .unwrap();
+            assert!(map.eq(loaded).unwrap());
+        });
+    }
+
+    #[test]
+    fn cbor_round_trip_rehashes_keys_on_decode() {
+        // Keys are re-derived from the decoded Python objects rather than
+        // trusted from the serialized stream, so lookups against the
+        // decoded map must still work for non-trivial (tuple) keys.
+        with_imrc_module(|py, module| {
+            let locals = [("imrc", module)].into_py_dict(py);
+            let map = py
+                .eval(
+                    "imrc.HashMap({(1, 'a'): 'first', (2, 'b'): 'second'})",
+                    None,
+                    Some(locals),
+                )
+                .unwrap();
+            let bytes = map.call_method0("to_cbor").unwrap();
+            let loaded = module
+                .getattr("HashMap")
+                .unwrap()
+                .call_method1("from_cbor", (bytes,))
+                .unwrap();
+            let value: String = loaded
+                .call_method1("get", ((1, "a"),))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(value, "first");
+        });
+    }
 }